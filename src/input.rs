@@ -10,6 +10,7 @@ pub use crate::stream::{BoxedStream, Stream};
 use super::*;
 #[cfg(feature = "memoization")]
 use hashbrown::HashMap;
+use core::cell::RefCell;
 
 /// A trait for types that represents a stream of input tokens. Unlike [`Iterator`], this type
 /// supports backtracking and a few other features required by the crate.
@@ -83,6 +84,76 @@ pub trait Input<'a>: 'a {
             context,
         }
     }
+
+    /// Mark this input as a partial (incomplete) buffer, such as a chunk of a network stream or a file being read
+    /// incrementally.
+    ///
+    /// This is infrastructure for primitives that need to distinguish genuine end-of-input from merely having
+    /// reached the currently-known end of an incomplete stream: [`InputRef::is_partial`] and
+    /// [`InputRef::next_token_or_needed`] let such a primitive report a [`Needed`] instead of failing outright. No
+    /// built-in primitive in this crate does so yet, and the public parse entry point does not yet surface a
+    /// `Needed`-bearing result variant, so marking an input `.partial()` does not by itself change any parser's
+    /// observable behaviour today. See [`Partial`].
+    fn partial(self) -> Partial<Self>
+    where
+        Self: Sized,
+    {
+        Partial { input: self }
+    }
+
+    /// Returns `true` if this input is a [`Partial`] buffer that may have more tokens appended to it later.
+    ///
+    /// Most inputs are complete, materialized buffers, so the default implementation returns `false`.
+    #[doc(hidden)]
+    fn is_partial(&self) -> bool {
+        false
+    }
+
+    /// Fold the case of tokens produced by this input, so that matchers like [`just`](crate::primitive::just) and
+    /// keyword/character-class parsers match regardless of the case of the input.
+    ///
+    /// Unlike lowercasing the whole input up front, the original, unmodified text is still available via
+    /// [`SliceInput::slice`] and [`SliceInput::slice_from`], so extracted identifiers and error spans preserve the
+    /// user's original casing. See [`Caseless`].
+    fn caseless(self) -> Caseless<Self>
+    where
+        Self: Sized,
+        Self::Token: AsciiFold,
+    {
+        Caseless { input: self }
+    }
+
+    /// Rebase the spans produced by this input by a fixed starting offset, so that a sub-parse run (via the
+    /// internal `with_input` machinery) over a derived buffer - such as the decoded body of a string literal, or
+    /// some other un-escaped or preprocessed region - reports spans in the coordinate system of the original
+    /// source rather than relative to the derived buffer. See [`Rebased`].
+    ///
+    /// This pairs naturally with [`Input::with_context`] for additionally attaching a file identifier.
+    fn rebased(self, base: usize) -> Rebased<Self>
+    where
+        Self: Input<'a, Offset = usize> + Sized,
+        Self::Span: Span<Offset = usize>,
+    {
+        Rebased { input: self, base }
+    }
+}
+
+// Case-fold a token to its ASCII-lowercase form. Implemented for the concrete token types `Caseless` supports.
+#[doc(hidden)]
+pub trait AsciiFold: Copy {
+    fn ascii_fold(self) -> Self;
+}
+
+impl AsciiFold for char {
+    fn ascii_fold(self) -> Self {
+        self.to_ascii_lowercase()
+    }
+}
+
+impl AsciiFold for u8 {
+    fn ascii_fold(self) -> Self {
+        self.to_ascii_lowercase()
+    }
 }
 
 /// A trait for types that represent slice-like streams of input tokens.
@@ -237,6 +308,73 @@ impl<'a, T: Clone + 'a, const N: usize> Input<'a> for &'a [T; N] {
 
 impl<'a, const N: usize> StrInput<'a, u8> for &'a [u8; N] {}
 
+/// An [`Input`] implementation that lazily pulls tokens from an arbitrary [`Iterator`], buffering them internally
+/// so that already-consumed tokens stay available for backtracking. See [`IterInput::new`].
+///
+/// This allows parsing straight from a streaming token source - such as a `logos` lexer, or any other custom
+/// tokenizer - without first collecting every token into a [`Vec`]. It composes with [`Input::spanned`] for
+/// iterators of `(T, S)` pairs, and works equally well over `Iterator<Item = Result<T, E>>` since the fallible
+/// token type is simply buffered and cloned like any other.
+pub struct IterInput<Iter: Iterator> {
+    // Fused so that EOI is sticky: once the iterator yields `None`, `fill` must never poll it again, even if the
+    // underlying iterator would otherwise be allowed (per the `Iterator` contract) to yield `Some` afterwards.
+    iter: RefCell<core::iter::Fuse<Iter>>,
+    buf: RefCell<Vec<Iter::Item>>,
+}
+
+impl<Iter: Iterator> IterInput<Iter> {
+    /// Create a new [`IterInput`] that pulls tokens from `iter` on demand as the parser advances.
+    pub fn new(iter: Iter) -> Self {
+        Self {
+            iter: RefCell::new(iter.fuse()),
+            buf: RefCell::new(Vec::new()),
+        }
+    }
+
+    // Ensure that the buffer holds a token at `idx`, pulling more from the iterator if required. Returns `false` if
+    // the iterator was exhausted before `idx` could be filled.
+    fn fill(&self, idx: usize) -> bool {
+        let mut buf = self.buf.borrow_mut();
+        while buf.len() <= idx {
+            match self.iter.borrow_mut().next() {
+                Some(tok) => buf.push(tok),
+                None => return false,
+            }
+        }
+        true
+    }
+}
+
+impl<'a, Iter> Input<'a> for IterInput<Iter>
+where
+    Iter: Iterator + 'a,
+    Iter::Item: Clone,
+{
+    type Offset = usize;
+    type Token = Iter::Item;
+    type Span = SimpleSpan<usize>;
+
+    fn start(&self) -> Self::Offset {
+        0
+    }
+
+    unsafe fn next(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::Token>) {
+        if self.fill(offset) {
+            (offset + 1, Some(self.buf.borrow()[offset].clone()))
+        } else {
+            (offset, None)
+        }
+    }
+
+    unsafe fn span(&self, range: Range<Self::Offset>) -> Self::Span {
+        range.into()
+    }
+
+    fn prev(offs: Self::Offset) -> Self::Offset {
+        offs.saturating_sub(1)
+    }
+}
+
 impl<'a, T: Clone + 'a, const N: usize> SliceInput<'a> for &'a [T; N] {
     type Slice = &'a [T];
 
@@ -305,6 +443,10 @@ where
     fn prev(offs: Self::Offset) -> Self::Offset {
         I::prev(offs)
     }
+
+    fn is_partial(&self) -> bool {
+        self.input.is_partial()
+    }
 }
 
 impl<'a, T, S, I> BorrowInput<'a> for SpannedInput<T, S, I>
@@ -366,6 +508,10 @@ where
     fn prev(offs: Self::Offset) -> Self::Offset {
         I::prev(offs)
     }
+
+    fn is_partial(&self) -> bool {
+        self.input.is_partial()
+    }
 }
 
 impl<'a, Ctx: Clone + 'a, I: BorrowInput<'a>> BorrowInput<'a> for WithContext<Ctx, I>
@@ -400,10 +546,218 @@ where
 {
 }
 
+/// Indicates how much more input is required before a parse run over a [`Partial`] input could make progress,
+/// rather than treating the currently-known end of the data as genuine end-of-input.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Needed {
+    /// At least this many more tokens are required before parsing can continue.
+    Size(usize),
+    /// More tokens are required, but it isn't known how many.
+    Unknown,
+}
+
+impl Needed {
+    // Combine two requirements arising from the same position, keeping the larger of the two.
+    pub(crate) fn combine(self, other: Self) -> Self {
+        match (self, other) {
+            (Needed::Size(a), Needed::Size(b)) => Needed::Size(a.max(b)),
+            _ => Needed::Unknown,
+        }
+    }
+}
+
+/// An input wrapper that marks the wrapped input as a partial, incomplete buffer being fed incrementally. See
+/// [`Input::partial`].
+#[derive(Copy, Clone)]
+pub struct Partial<I> {
+    input: I,
+}
+
+impl<'a, I: Input<'a>> Input<'a> for Partial<I> {
+    type Offset = I::Offset;
+    type Token = I::Token;
+    type Span = I::Span;
+
+    fn start(&self) -> Self::Offset {
+        self.input.start()
+    }
+
+    unsafe fn next(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::Token>) {
+        self.input.next(offset)
+    }
+
+    unsafe fn span(&self, range: Range<Self::Offset>) -> Self::Span {
+        self.input.span(range)
+    }
+
+    fn prev(offs: Self::Offset) -> Self::Offset {
+        I::prev(offs)
+    }
+
+    fn is_partial(&self) -> bool {
+        true
+    }
+}
+
+impl<'a, I: BorrowInput<'a>> BorrowInput<'a> for Partial<I> {
+    unsafe fn next_ref(&self, offset: Self::Offset) -> (Self::Offset, Option<&'a Self::Token>) {
+        self.input.next_ref(offset)
+    }
+}
+
+impl<'a, I: SliceInput<'a>> SliceInput<'a> for Partial<I> {
+    type Slice = I::Slice;
+
+    fn slice(&self, range: Range<Self::Offset>) -> Self::Slice {
+        <I as SliceInput>::slice(&self.input, range)
+    }
+    fn slice_from(&self, from: RangeFrom<Self::Offset>) -> Self::Slice {
+        <I as SliceInput>::slice_from(&self.input, from)
+    }
+}
+
+impl<'a, C: Char, I: StrInput<'a, C>> StrInput<'a, C> for Partial<I> {}
+
+/// An input wrapper that case-folds the tokens produced by the wrapped input, while leaving slices and spans
+/// untouched. See [`Input::caseless`].
+#[derive(Copy, Clone)]
+pub struct Caseless<I> {
+    input: I,
+}
+
+impl<'a, I> Input<'a> for Caseless<I>
+where
+    I: Input<'a>,
+    I::Token: AsciiFold,
+{
+    type Offset = I::Offset;
+    type Token = I::Token;
+    type Span = I::Span;
+
+    fn start(&self) -> Self::Offset {
+        self.input.start()
+    }
+
+    unsafe fn next(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::Token>) {
+        let (offset, tok) = self.input.next(offset);
+        (offset, tok.map(AsciiFold::ascii_fold))
+    }
+
+    unsafe fn span(&self, range: Range<Self::Offset>) -> Self::Span {
+        self.input.span(range)
+    }
+
+    fn prev(offs: Self::Offset) -> Self::Offset {
+        I::prev(offs)
+    }
+
+    fn is_partial(&self) -> bool {
+        self.input.is_partial()
+    }
+}
+
+impl<'a, I> SliceInput<'a> for Caseless<I>
+where
+    I: SliceInput<'a>,
+    I::Token: AsciiFold,
+{
+    type Slice = I::Slice;
+
+    fn slice(&self, range: Range<Self::Offset>) -> Self::Slice {
+        <I as SliceInput>::slice(&self.input, range)
+    }
+    fn slice_from(&self, from: RangeFrom<Self::Offset>) -> Self::Slice {
+        <I as SliceInput>::slice_from(&self.input, from)
+    }
+}
+
+impl<'a, C, I> StrInput<'a, C> for Caseless<I>
+where
+    I: StrInput<'a, C>,
+    C: Char + AsciiFold,
+{
+}
+
+/// An input wrapper that rebases the spans produced by the wrapped input by a fixed starting offset. See
+/// [`Input::rebased`].
+///
+/// `next`, `slice` and rewinding via [`Marker`] all operate on the inner input's own, un-rebased offsets, exactly
+/// as required by the safety contract on [`Input::next`]/[`Input::span`]. Only the final [`Span`] produced by
+/// [`Input::span`] is affected: the inner input is asked for the span in its own coordinates first, and only the
+/// resulting `start`/`end` are shifted by `base`, so wrapping an input whose `span` implementation depends on more
+/// than the raw offsets (such as [`SpannedInput`]) still works correctly.
+#[derive(Copy, Clone)]
+pub struct Rebased<I> {
+    input: I,
+    base: usize,
+}
+
+impl<'a, I> Input<'a> for Rebased<I>
+where
+    I: Input<'a, Offset = usize>,
+    I::Span: Span<Offset = usize>,
+{
+    type Offset = usize;
+    type Token = I::Token;
+    type Span = I::Span;
+
+    fn start(&self) -> Self::Offset {
+        self.input.start()
+    }
+
+    unsafe fn next(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::Token>) {
+        self.input.next(offset)
+    }
+
+    unsafe fn span(&self, range: Range<Self::Offset>) -> Self::Span {
+        let span = self.input.span(range);
+        Span::new(
+            span.context(),
+            (span.start() + self.base)..(span.end() + self.base),
+        )
+    }
+
+    fn prev(offs: Self::Offset) -> Self::Offset {
+        I::prev(offs)
+    }
+
+    fn is_partial(&self) -> bool {
+        self.input.is_partial()
+    }
+}
+
+impl<'a, I> BorrowInput<'a> for Rebased<I>
+where
+    I: BorrowInput<'a> + Input<'a, Offset = usize>,
+    I::Span: Span<Offset = usize>,
+{
+    unsafe fn next_ref(&self, offset: Self::Offset) -> (Self::Offset, Option<&'a Self::Token>) {
+        self.input.next_ref(offset)
+    }
+}
+
+impl<'a, I> SliceInput<'a> for Rebased<I>
+where
+    I: SliceInput<'a> + Input<'a, Offset = usize>,
+    I::Span: Span<Offset = usize>,
+{
+    type Slice = I::Slice;
+
+    fn slice(&self, range: Range<Self::Offset>) -> Self::Slice {
+        <I as SliceInput>::slice(&self.input, range)
+    }
+    fn slice_from(&self, from: RangeFrom<Self::Offset>) -> Self::Slice {
+        <I as SliceInput>::slice_from(&self.input, from)
+    }
+}
+
+impl<'a, C: Char, I: StrInput<'a, C>> StrInput<'a, C> for Rebased<I> where I::Span: Span<Offset = usize> {}
+
 /// Represents the progress of a parser through the input
 pub struct Marker<'a, I: Input<'a>> {
     pub(crate) offset: I::Offset,
     err_count: usize,
+    needed: Option<Needed>,
 }
 
 impl<'a, I: Input<'a>> Copy for Marker<'a, I> {}
@@ -416,6 +770,9 @@ impl<'a, I: Input<'a>> Clone for Marker<'a, I> {
 pub(crate) struct Errors<E> {
     pub(crate) alt: Option<Located<E>>,
     pub(crate) secondary: Vec<E>,
+    // The largest `Needed` reported by any primitive that hit partial-EOI during this parse, if any. See
+    // `InputRef::add_needed`.
+    pub(crate) needed: Option<Needed>,
 }
 
 impl<E> Default for Errors<E> {
@@ -423,6 +780,7 @@ impl<E> Default for Errors<E> {
         Self {
             alt: None,
             secondary: Vec::new(),
+            needed: None,
         }
     }
 }
@@ -431,6 +789,9 @@ impl<E> Default for Errors<E> {
 pub struct InputRef<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> {
     pub(crate) input: &'parse I,
     pub(crate) offset: I::Offset,
+    // Whether `input` is a `Partial` buffer, i.e: whether reaching `offset` means genuine end-of-input or just the
+    // currently-known end of an incomplete stream. See `InputRef::needed`.
+    pub(crate) partial: bool,
     pub(crate) errors: Errors<E::Error>,
     // TODO: Don't use a result, use something like `Cow` but that allows `E::State` to not be `Clone`
     pub(crate) state: &'parse mut E::State,
@@ -447,6 +808,7 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     {
         Self {
             offset: input.start(),
+            partial: input.is_partial(),
             input,
             state,
             ctx: Some(E::Context::default()),
@@ -470,6 +832,7 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
         let mut new_inp = InputRef {
             input: self.input,
             offset: self.offset,
+            partial: self.partial,
             state: self.state,
             ctx: Some(new_ctx),
             errors: mem::replace(&mut self.errors, Errors::default()),
@@ -494,6 +857,7 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
 
         let mut new_inp = InputRef {
             offset: new_input.start(),
+            partial: new_input.is_partial(),
             input: new_input,
             state: self.state,
             ctx: self.ctx.take(),
@@ -519,6 +883,7 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
         Marker {
             offset: self.offset,
             err_count: self.errors.secondary.len(),
+            needed: self.errors.needed,
         }
     }
 
@@ -526,6 +891,7 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     #[inline]
     pub fn rewind(&mut self, marker: Marker<'a, I>) {
         self.errors.secondary.truncate(marker.err_count);
+        self.errors.needed = marker.needed;
         self.offset = marker.offset;
     }
 
@@ -578,6 +944,29 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
         self.next().1
     }
 
+    /// Get the next token in the input, distinguishing genuine end-of-input from merely having reached the
+    /// currently-known end of a [`Partial`] buffer.
+    ///
+    /// Returns `Ok(token)` if a token was available. Otherwise returns `Err(Some(needed))` if the input is partial
+    /// (so buffering more data and re-running the parse from the start might succeed), or `Err(None)` if this is
+    /// genuine end-of-input. This is the primitive that a future protocol/stream-aware combinator should use
+    /// instead of [`InputRef::next_token`] when it needs to tell a caller "buffer more and retry" apart from
+    /// "parsing is complete" — no built-in combinator does so yet, and the recorded [`Needed`] is not yet surfaced
+    /// through the public parse result, so this method is foundation for that integration rather than a complete
+    /// end-to-end feature.
+    pub fn next_token_or_needed(&mut self) -> Result<I::Token, Option<Needed>> {
+        match self.next_token() {
+            Some(tok) => Ok(tok),
+            None => {
+                let needed = self.needed(1);
+                if let Some(needed) = needed {
+                    self.add_needed(needed);
+                }
+                Err(needed)
+            }
+        }
+    }
+
     /// Peek the next token in the input. Returns `None` for EOI
     pub fn peek(&self) -> Option<I::Token> {
         // SAFETY: offset was generated by previous call to `Input::next`
@@ -590,6 +979,39 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
         let _ = self.next();
     }
 
+    /// Returns `true` if the underlying input is a [`Partial`] buffer, i.e: reaching the current offset does not
+    /// necessarily mean genuine end-of-input.
+    #[inline]
+    pub(crate) fn is_partial(&self) -> bool {
+        self.partial
+    }
+
+    /// Called by a primitive that has reached the current end of the input but needs at least `required` more
+    /// tokens to succeed. If the input is partial, this reports the shortfall as a [`Needed`] so that error
+    /// reporting can distinguish "more input might fix this" from genuine end-of-input; otherwise returns `None`.
+    #[inline]
+    pub(crate) fn needed(&self, required: usize) -> Option<Needed> {
+        self.partial.then_some(Needed::Size(required))
+    }
+
+    /// Record that some primitive needed more input than was available. If more than one primitive reports a
+    /// requirement at the same position (for example, several branches of an `or` all running out of partial
+    /// input), the largest of the two is kept via [`Needed::combine`].
+    #[inline]
+    pub(crate) fn add_needed(&mut self, needed: Needed) {
+        self.errors.needed = Some(match self.errors.needed.take() {
+            Some(existing) => existing.combine(needed),
+            None => needed,
+        });
+    }
+
+    /// Take the largest [`Needed`] recorded during this parse, if any primitive reported running out of partial
+    /// input. See [`InputRef::next_token_or_needed`].
+    #[inline]
+    pub(crate) fn take_needed(&mut self) -> Option<Needed> {
+        self.errors.needed.take()
+    }
+
     #[inline]
     pub(crate) fn slice(&self, range: Range<I::Offset>) -> I::Slice
     where
@@ -671,3 +1093,273 @@ impl<E> Emitter<E> {
         self.emitted.push(err)
     }
 }
+
+/// A helper that resolves the byte [`Offset`](Input::Offset)s used internally by chumsky into `(line, column)`
+/// pairs for a [`StrInput`], for use when reporting diagnostics to a user.
+///
+/// The byte offset of each line start is computed lazily on first use and then cached, so that resolving many
+/// spans (as is typical when rendering a batch of diagnostics) only pays the `O(n)` scan over the source once,
+/// with each subsequent lookup costing `O(log n)`.
+///
+/// By default, both lines and columns are zero-based; call [`SourceMap::one_indexed`] to switch to the 1-based
+/// numbering conventional in most editors and diagnostic formats.
+pub struct SourceMap<'a> {
+    src: &'a str,
+    base: usize,
+    line_starts: RefCell<Option<Vec<usize>>>,
+}
+
+impl<'a> SourceMap<'a> {
+    /// Create a [`SourceMap`] over the given source string, using zero-based line and column numbers.
+    pub fn new(src: &'a str) -> Self {
+        Self {
+            src,
+            base: 0,
+            line_starts: RefCell::new(None),
+        }
+    }
+
+    /// Switch this [`SourceMap`] to report 1-based line and column numbers.
+    pub fn one_indexed(mut self) -> Self {
+        self.base = 1;
+        self
+    }
+
+    fn line_starts(&self) -> core::cell::Ref<[usize]> {
+        {
+            let mut cache = self.line_starts.borrow_mut();
+            if cache.is_none() {
+                let mut starts = Vec::with_capacity(16);
+                starts.push(0);
+                starts.extend(self.src.match_indices('\n').map(|(i, _)| i + 1));
+                *cache = Some(starts);
+            }
+        }
+        core::cell::Ref::map(self.line_starts.borrow(), |starts| {
+            starts.as_deref().unwrap()
+        })
+    }
+
+    // Clamp `offset` into `[0, src.len()]` and round down to the nearest char boundary at or before it, so that
+    // callers passing a byte offset that splits a multi-byte UTF-8 character (or that simply runs past the end of
+    // the source) never trigger a slicing panic below.
+    fn clamp_to_char_boundary(&self, offset: usize) -> usize {
+        let mut offset = offset.min(self.src.len());
+        while !self.src.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    }
+
+    // Resolve `offset` to a (line index, byte offset of that line's start) pair. `offset` must already be a valid
+    // char boundary within `src`, as produced by `clamp_to_char_boundary`.
+    fn line_of(&self, offset: usize) -> (usize, usize) {
+        let starts = self.line_starts();
+        let line = match starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        };
+        (line, starts[line])
+    }
+
+    /// Resolve a byte `offset` into this source to a `(line, column)` pair, with the column measured in Unicode
+    /// scalar values (`char`s).
+    ///
+    /// `offset` is clamped to the length of the source and, if it falls in the middle of a multi-byte UTF-8
+    /// character, rounded down to that character's start, rather than panicking.
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let offset = self.clamp_to_char_boundary(offset);
+        let (line, line_start) = self.line_of(offset);
+        let col = self.src[line_start..offset].chars().count();
+        (line + self.base, col + self.base)
+    }
+
+    /// As [`SourceMap::line_col`], but the column is measured in UTF-16 code units, as required by protocols such
+    /// as the Language Server Protocol.
+    ///
+    /// `offset` is clamped to the length of the source and, if it falls in the middle of a multi-byte UTF-8
+    /// character, rounded down to that character's start, rather than panicking.
+    pub fn line_col_utf16(&self, offset: usize) -> (usize, usize) {
+        let offset = self.clamp_to_char_boundary(offset);
+        let (line, line_start) = self.line_of(offset);
+        let col: usize = self.src[line_start..offset]
+            .chars()
+            .map(char::len_utf16)
+            .sum();
+        (line + self.base, col + self.base)
+    }
+
+    /// Resolve a [`SimpleSpan`] to the `(line, column)` pair of its start offset.
+    pub fn span_start(&self, span: SimpleSpan<usize>) -> (usize, usize) {
+        self.line_col(span.start())
+    }
+
+    /// Resolve a [`SimpleSpan`] to the `(line, column)` pair of its end offset.
+    pub fn span_end(&self, span: SimpleSpan<usize>) -> (usize, usize) {
+        self.line_col(span.end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_marks_input_as_partial() {
+        assert!(!"abc".is_partial());
+        assert!("abc".partial().is_partial());
+    }
+
+    #[test]
+    fn needed_combine_keeps_larger_requirement() {
+        assert_eq!(Needed::Size(2).combine(Needed::Size(5)), Needed::Size(5));
+        assert_eq!(Needed::Size(5).combine(Needed::Size(2)), Needed::Size(5));
+        assert_eq!(Needed::Size(2).combine(Needed::Unknown), Needed::Unknown);
+    }
+
+    #[test]
+    fn source_map_resolves_lines_and_columns() {
+        let src = "ab\ncd\n\nef";
+        let map = SourceMap::new(src);
+
+        // Start of the source.
+        assert_eq!(map.line_col(0), (0, 0));
+        // Offset exactly at a `\n`: still part of the line it terminates.
+        assert_eq!(map.line_col(2), (0, 2));
+        // Just after the `\n`: the first column of the next line.
+        assert_eq!(map.line_col(3), (1, 0));
+        // An empty line.
+        assert_eq!(map.line_col(6), (2, 0));
+        // End of the source (one past the last byte).
+        assert_eq!(map.line_col(src.len()), (3, 2));
+        // Offsets past the end of the source are clamped rather than panicking.
+        assert_eq!(map.line_col(src.len() + 10), (3, 2));
+    }
+
+    #[test]
+    fn source_map_empty_input_is_a_single_line() {
+        let map = SourceMap::new("");
+        assert_eq!(map.line_col(0), (0, 0));
+    }
+
+    #[test]
+    fn source_map_one_indexed() {
+        let map = SourceMap::new("ab\ncd").one_indexed();
+        assert_eq!(map.line_col(0), (1, 1));
+        assert_eq!(map.line_col(3), (2, 1));
+    }
+
+    #[test]
+    fn source_map_utf16_columns_differ_from_scalar_columns() {
+        // "𝄞" is one Unicode scalar value but two UTF-16 code units.
+        let src = "𝄞x";
+        let map = SourceMap::new(src);
+        let end = src.len();
+        assert_eq!(map.line_col(end), (0, 2));
+        assert_eq!(map.line_col_utf16(end), (0, 3));
+    }
+
+    #[test]
+    fn source_map_rounds_down_offsets_that_split_a_char() {
+        // "𝄞" is a 4-byte UTF-8 character starting at byte 0; offsets 1-3 fall inside it.
+        let src = "𝄞x";
+        let map = SourceMap::new(src);
+        for offset in 1..4 {
+            assert_eq!(map.line_col(offset), (0, 0));
+            assert_eq!(map.line_col_utf16(offset), (0, 0));
+        }
+    }
+
+    #[test]
+    fn iter_input_sticky_eoi_after_exhaustion() {
+        // An iterator that is legal per the `Iterator` contract but not "fused": it yields `None` once, then
+        // `Some` again afterwards.
+        struct Flaky(std::vec::IntoIter<u8>, bool);
+        impl Iterator for Flaky {
+            type Item = u8;
+            fn next(&mut self) -> Option<u8> {
+                if let Some(tok) = self.0.next() {
+                    return Some(tok);
+                }
+                if !self.1 {
+                    self.1 = true;
+                    return None;
+                }
+                Some(255)
+            }
+        }
+
+        let input = IterInput::new(Flaky(vec![1u8, 2, 3].into_iter(), false));
+        unsafe {
+            assert_eq!(input.next(0), (1, Some(1)));
+            assert_eq!(input.next(1), (2, Some(2)));
+            assert_eq!(input.next(2), (3, Some(3)));
+            // The iterator is now exhausted. Despite `Flaky` being willing to yield `Some(255)` on a further poll,
+            // `IterInput` must report end-of-input forever after the first `None`.
+            assert_eq!(input.next(3), (3, None));
+            assert_eq!(input.next(3), (3, None));
+        }
+    }
+
+    #[test]
+    fn iter_input_retains_buffer_for_backtracking() {
+        let input = IterInput::new(vec!['a', 'b', 'c'].into_iter());
+        unsafe {
+            let (o1, t1) = input.next(input.start());
+            assert_eq!(t1, Some('a'));
+            let (_o2, t2) = input.next(o1);
+            assert_eq!(t2, Some('b'));
+            // Rewind to the start and re-read: already-buffered tokens must still be available.
+            let (o1_again, t1_again) = input.next(input.start());
+            assert_eq!((o1_again, t1_again), (o1, Some('a')));
+        }
+    }
+
+    #[test]
+    fn caseless_folds_tokens_but_preserves_slices() {
+        let input = "SeLeCt".caseless();
+        let mut offset = input.start();
+        let mut folded = String::new();
+        unsafe {
+            loop {
+                let (next_offset, tok) = input.next(offset);
+                match tok {
+                    Some(c) => folded.push(c),
+                    None => break,
+                }
+                offset = next_offset;
+            }
+        }
+        assert_eq!(folded, "select");
+        // The original casing is preserved when slicing, not the folded tokens.
+        assert_eq!(input.slice(0..6), "SeLeCt");
+    }
+
+    #[test]
+    fn caseless_forwards_is_partial() {
+        assert!(!"abc".caseless().is_partial());
+        assert!("abc".partial().caseless().is_partial());
+    }
+
+    #[test]
+    fn rebased_shifts_resolved_span_not_raw_offsets() {
+        // A `SpannedInput`, whose own `span` impl re-queries `next` on the raw offsets rather than just echoing
+        // them back - the exact shape that broke when `Rebased` shifted offsets before delegating.
+        let toks: &[(char, SimpleSpan<usize>)] = &[
+            ('a', SimpleSpan::from(10..11)),
+            ('b', SimpleSpan::from(11..12)),
+        ];
+        let spanned = toks.spanned(SimpleSpan::from(12..12));
+        let rebased = spanned.rebased(100);
+
+        let span = unsafe { rebased.span(0..2) };
+        assert_eq!(span.start(), 110);
+        assert_eq!(span.end(), 112);
+    }
+
+    #[test]
+    fn rebased_forwards_is_partial() {
+        assert!(!"abc".rebased(10).is_partial());
+        assert!("abc".partial().rebased(10).is_partial());
+    }
+}